@@ -1,15 +1,14 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration, time::SystemTime};
 
 use rustls::{
-    cipher_suite::{
-        TLS13_AES_128_GCM_SHA256, TLS13_AES_256_GCM_SHA384, TLS13_CHACHA20_POLY1305_SHA256,
-        TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256, TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
-        TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256, TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
-        TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384, TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    server::{
+        AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient,
+        ClientCertVerified, ClientCertVerifier, DistinguishedNames, NoClientAuth,
+        ResolvesServerCertUsingSni,
     },
-    server::{NoClientAuth, ResolvesServerCertUsingSni},
     sign::{any_supported_type, CertifiedKey},
-    ServerConfig, SupportedCipherSuite, ALL_CIPHER_SUITES, ALL_KX_GROUPS, ALL_VERSIONS,
+    Certificate, Error as TlsError, RootCertStore, ServerConfig, SupportedCipherSuite,
+    ALL_CIPHER_SUITES, ALL_KX_GROUPS, ALL_VERSIONS,
 };
 use tokio::net::TcpSocket;
 
@@ -19,6 +18,130 @@ use super::{
     Config, Listener, Server, ServerProtocol,
 };
 
+
+/// Writes NSS-format key-log lines (`CLIENT_RANDOM <hex> <hex>` and the
+/// TLS 1.3 secret labels) to an append-only file, for decrypting captured
+/// traffic in Wireshark. Only ever installed when explicitly configured.
+struct KeyLogFile {
+    writer: std::sync::Mutex<std::io::BufWriter<std::fs::File>>,
+}
+
+impl KeyLogFile {
+    fn open(path: &str) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(KeyLogFile {
+            writer: std::sync::Mutex::new(std::io::BufWriter::new(file)),
+        })
+    }
+}
+
+impl rustls::KeyLog for KeyLogFile {
+    fn log(&self, label: &str, client_random: &[u8], secret: &[u8]) {
+        use std::io::Write;
+
+        let mut line = String::with_capacity(label.len() + client_random.len() * 2 + secret.len() * 2 + 3);
+        line.push_str(label);
+        line.push(' ');
+        for byte in client_random {
+            line.push_str(&format!("{:02x}", byte));
+        }
+        line.push(' ');
+        for byte in secret {
+            line.push_str(&format!("{:02x}", byte));
+        }
+        line.push('\n');
+
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.write_all(line.as_bytes());
+            let _ = writer.flush();
+        }
+    }
+}
+
+static SESSIONS_RESUMED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+static SESSIONS_FULL: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Returns `(resumed, full)` counts since startup, taken from the session-ID
+/// cache: `resumed` is the number of cache hits and `full` the number of
+/// sessions stored in it. Resumptions through session tickets do not go
+/// through this cache and are not counted.
+pub fn session_resumption_counters() -> (u64, u64) {
+    (
+        SESSIONS_RESUMED.load(std::sync::atomic::Ordering::Relaxed),
+        SESSIONS_FULL.load(std::sync::atomic::Ordering::Relaxed),
+    )
+}
+
+/// A bounded, TTL-expiring `rustls` server session-ID cache. Unlike
+/// `rustls::server::ServerSessionMemoryCache`, entries are evicted once they
+/// exceed `ttl`, not just when the LRU capacity is reached.
+struct TtlSessionCache {
+    entries: std::sync::Mutex<std::collections::HashMap<Vec<u8>, (Vec<u8>, std::time::Instant)>>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl TtlSessionCache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        TtlSessionCache {
+            entries: std::sync::Mutex::new(std::collections::HashMap::new()),
+            capacity,
+            ttl,
+        }
+    }
+}
+
+impl rustls::server::StoresServerSessions for TtlSessionCache {
+    fn put(&self, key: Vec<u8>, value: Vec<u8>) -> bool {
+        SESSIONS_FULL.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (_, inserted))| *inserted)
+                .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (value, std::time::Instant::now()));
+        true
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((value, inserted)) if inserted.elapsed() < self.ttl => {
+                SESSIONS_RESUMED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Some(value.clone())
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn take(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.remove(key) {
+            Some((value, inserted)) if inserted.elapsed() < self.ttl => {
+                SESSIONS_RESUMED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    fn can_cache(&self) -> bool {
+        true
+    }
+}
+
 impl Config {
     pub fn build_servers(&self) -> super::Result<Vec<Server>> {
         let mut servers: Vec<Server> = Vec::new();
@@ -40,6 +163,40 @@ impl Config {
         }
     }
 
+    /// Loads the DER-encoded OCSP response configured for `cert_id` under
+    /// `certificate.<cert_id>.ocsp-response`, if any.
+    ///
+    /// This is static, file-based stapling only: the response is read once
+    /// at config load/rebuild time and served verbatim until the config is
+    /// reloaded. A background refresher that fetches a fresh response ahead
+    /// of `nextUpdate` and hot-swaps it into the certificate resolver was
+    /// attempted and reverted (its OCSP request wasn't valid DER, and the
+    /// resolver never consulted its output); that resolver-side plumbing
+    /// doesn't exist in this tree, so there's nothing for a refresher to
+    /// feed. The only check made here is that the configured file starts
+    /// with a DER SEQUENCE tag (0x30), so a file that is plainly not DER
+    /// doesn't get stapled into every handshake silently.
+    fn load_ocsp_response(&self, cert_id: &str) -> super::Result<Option<Vec<u8>>> {
+        match self.value(("certificate", cert_id, "ocsp-response")) {
+            Some(path) => {
+                let data = std::fs::read(path).map_err(|err| {
+                    format!(
+                        "Failed to read OCSP response {:?} for certificate {:?}: {}",
+                        path, cert_id, err
+                    )
+                })?;
+                if data.first() != Some(&0x30) {
+                    return Err(format!(
+                        "OCSP response {:?} for certificate {:?} is not a DER-encoded sequence",
+                        path, cert_id
+                    ));
+                }
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn build_server(&self, id: &str) -> super::Result<Server> {
         // Build TLS config
         let (tls, tls_implicit) = if self
@@ -65,12 +222,40 @@ impl Config {
                 }
             }
 
-            // Parse cipher suites
+            // Parse cipher suites, resolved by name against the suites this
+            // build's rustls backend actually advertises rather than a fixed
+            // match arm, so a new suite only needs adding to `ALL_CIPHER_SUITES`.
             let mut ciphers = Vec::new();
             for (key, protocol) in
                 self.values_or_default(("server.listener", id, "tls.cipher"), "server.tls.cipher")
             {
-                ciphers.push(protocol.parse_key(key)?);
+                ciphers.push(find_cipher_suite(protocol).ok_or_else(|| {
+                    format!(
+                        "Unsupported TLS cipher suite {:?} found in key {:?}",
+                        protocol, key
+                    )
+                })?);
+            }
+
+            // Validate the selected crypto backend. rustls 0.20, the version
+            // this crate is pinned to, only ships a ring-backed suite/kx-group
+            // implementation (`ALL_CIPHER_SUITES`/`ALL_KX_GROUPS` above are
+            // ring's), so "ring" is the only value this build can honor today.
+            // We still parse and validate the directive, rather than ignoring
+            // it, so a config asking for e.g. "aws-lc-rs" or "fips" fails loudly
+            // at startup instead of silently running on the wrong backend.
+            // Swapping in an alternate backend requires upgrading to a rustls
+            // version that exposes a pluggable `CryptoProvider`.
+            if let Some(provider) = self.property_or_default::<String>(
+                ("server.listener", id, "tls.crypto-provider"),
+                "server.tls.crypto-provider",
+            )? {
+                if provider != "ring" {
+                    return Err(format!(
+                        "Unsupported crypto provider {:?}: this build only supports \"ring\"",
+                        provider
+                    ));
+                }
             }
 
             // Obtain default certificate
@@ -104,7 +289,7 @@ impl Config {
                                                 key, err
                                             )
                                         })?,
-                                    ocsp: None,
+                                    ocsp: self.load_ocsp_response(sni_cert_id)?,
                                     sct_list: None,
                                 },
                                 _ => CertifiedKey {
@@ -115,7 +300,7 @@ impl Config {
                                             key, err
                                         )
                                     })?,
-                                    ocsp: None,
+                                    ocsp: self.load_ocsp_response(cert_id)?,
                                     sct_list: None,
                                 },
                             },
@@ -126,13 +311,99 @@ impl Config {
                 }
             }
 
+            // Build the client certificate verifier (mTLS)
+            let client_auth_mode = self
+                .property_or_default::<ClientAuthMode>(
+                    ("server.listener", id, "tls.client-auth.mode"),
+                    "server.tls.client-auth.mode",
+                )?
+                .unwrap_or(ClientAuthMode::None);
+            let client_cert_verifier: Arc<dyn ClientCertVerifier> = if client_auth_mode
+                != ClientAuthMode::None
+            {
+                let ca_cert_id = self
+                    .value_or_default(
+                        ("server.listener", id, "tls.client-auth.ca-certificate"),
+                        "server.tls.client-auth.ca-certificate",
+                    )
+                    .ok_or_else(|| {
+                        format!(
+                            "Undefined client CA certificate id for listener {:?}.",
+                            id
+                        )
+                    })?;
+                let mut root_store = RootCertStore::empty();
+                root_store
+                    .add(&self.rustls_certificate(ca_cert_id)?)
+                    .map_err(|err| {
+                        format!(
+                            "Failed to add CA certificate {:?} to root store: {}",
+                            ca_cert_id, err
+                        )
+                    })?;
+
+                let inner = if client_auth_mode == ClientAuthMode::Required {
+                    AllowAnyAuthenticatedClient::new(root_store)
+                } else {
+                    AllowAnyAnonymousOrAuthenticatedClient::new(root_store)
+                };
+
+                // Load any configured CRLs and wrap the base verifier with a
+                // revocation check.
+                let mut revoked_serials = Vec::new();
+                for (key, path) in self.values_or_default(
+                    ("server.listener", id, "tls.client-auth.crl"),
+                    "server.tls.client-auth.crl",
+                ) {
+                    revoked_serials.extend(parse_crl(path).map_err(|err| {
+                        format!("Failed to parse CRL {:?} for key {:?}: {}", path, key, err)
+                    })?);
+                }
+
+                let inner: Arc<dyn ClientCertVerifier> = if !revoked_serials.is_empty() {
+                    let leaf_only = self
+                        .value_or_default(
+                            ("server.listener", id, "tls.client-auth.revocation-policy"),
+                            "server.tls.client-auth.revocation-policy",
+                        )
+                        .unwrap_or("chain")
+                        == "leaf";
+                    let allow_unknown = self
+                        .property_or_default(
+                            (
+                                "server.listener",
+                                id,
+                                "tls.client-auth.allow-unknown-revocation-status",
+                            ),
+                            "server.tls.client-auth.allow-unknown-revocation-status",
+                        )?
+                        .unwrap_or(false);
+
+                    Arc::new(RevocationCheckingVerifier {
+                        inner,
+                        revoked_serials,
+                        leaf_only,
+                        allow_unknown,
+                    })
+                } else {
+                    inner
+                };
+
+                // Capture the peer's extracted identity on every successful
+                // handshake so the session layer can stash it on
+                // `Session::data.tls_client_subject`.
+                Arc::new(IdentityCapturingVerifier { inner })
+            } else {
+                NoClientAuth::new()
+            };
+
             // Add default certificate
             let default_cert = Some(Arc::new(CertifiedKey {
                 cert: vec![cert],
                 key: any_supported_type(&pki).map_err(|err| {
                     format!("Failed to sign certificate id {:?}: {}", cert_id, err)
                 })?,
-                ocsp: None,
+                ocsp: self.load_ocsp_response(cert_id)?,
                 sct_list: None,
             }));
 
@@ -152,13 +423,52 @@ impl Config {
                     TLS12_VERSION
                 })
                 .map_err(|err| format!("Failed to build TLS config: {}", err))?
-                .with_client_cert_verifier(NoClientAuth::new())
+                .with_client_cert_verifier(client_cert_verifier)
                 .with_cert_resolver(Arc::new(CertificateResolver {
                     resolver: if has_sni { resolver.into() } else { None },
                     default_cert,
                 }));
 
-            //config.key_log = Arc::new(KeyLogger::default());
+            // SSLKEYLOGFILE support, opt-in only: used to decrypt captured
+            // SMTP-over-TLS traffic in Wireshark for interop diagnostics.
+            let key_log_path = self
+                .value_or_default(("server.listener", id, "tls.key-log"), "server.tls.key-log")
+                .map(str::to_string)
+                .or_else(|| std::env::var("SSLKEYLOGFILE").ok());
+            if let Some(path) = key_log_path {
+                config.key_log = Arc::new(
+                    KeyLogFile::open(&path)
+                        .map_err(|err| format!("Failed to open SSLKEYLOGFILE {:?}: {}", path, err))?,
+                );
+            }
+
+            // Session resumption: senders with queued mail reconnect often, so
+            // resumption meaningfully cuts handshake CPU and latency.
+            if self
+                .property_or_default(
+                    ("server.listener", id, "tls.session-tickets"),
+                    "server.tls.session-tickets",
+                )?
+                .unwrap_or(false)
+            {
+                config.ticketer = rustls::Ticketer::new()
+                    .map_err(|err| format!("Failed to create TLS session ticketer: {}", err))?;
+            }
+            config.session_storage = Arc::new(TtlSessionCache::new(
+                self.property_or_default(
+                    ("server.listener", id, "tls.session-cache.size"),
+                    "server.tls.session-cache.size",
+                )?
+                .unwrap_or(256usize),
+                Duration::from_secs(
+                    self.property_or_default(
+                        ("server.listener", id, "tls.session-cache.ttl"),
+                        "server.tls.session-cache.ttl",
+                    )?
+                    .unwrap_or(300u64),
+                ),
+            ));
+
             config.ignore_client_order = self
                 .property_or_default(
                     ("server.listener", id, "tls.ignore_client_order"),
@@ -272,6 +582,187 @@ impl Config {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientAuthMode {
+    None,
+    Optional,
+    Required,
+}
+
+impl ParseValue for ClientAuthMode {
+    fn parse_value(key: impl AsKey, value: &str) -> super::Result<Self> {
+        match value {
+            "none" => Ok(ClientAuthMode::None),
+            "optional" => Ok(ClientAuthMode::Optional),
+            "required" => Ok(ClientAuthMode::Required),
+            _ => Err(format!(
+                "Invalid client authentication mode {:?} for property {:?}.",
+                value,
+                key.as_key()
+            )),
+        }
+    }
+}
+
+/// Wraps a base `ClientCertVerifier` with a revocation check against one or
+/// more loaded CRLs, rejecting the handshake when the presented chain (or,
+/// with `revocation-policy = "leaf"`, just the end-entity certificate)
+/// contains a revoked serial number.
+struct RevocationCheckingVerifier {
+    inner: Arc<dyn ClientCertVerifier>,
+    revoked_serials: Vec<Vec<u8>>,
+    leaf_only: bool,
+    allow_unknown: bool,
+}
+
+impl ClientCertVerifier for RevocationCheckingVerifier {
+    fn offer_client_auth(&self) -> bool {
+        self.inner.offer_client_auth()
+    }
+
+    fn client_auth_mandatory(&self) -> Option<bool> {
+        self.inner.client_auth_mandatory()
+    }
+
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        self.inner.client_auth_root_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        now: SystemTime,
+    ) -> Result<ClientCertVerified, TlsError> {
+        let verified = self.inner.verify_client_cert(end_entity, intermediates, now)?;
+
+        let chain: Vec<&Certificate> = if self.leaf_only {
+            vec![end_entity]
+        } else {
+            std::iter::once(end_entity).chain(intermediates.iter()).collect()
+        };
+
+        for cert in chain {
+            match certificate_serial(cert) {
+                Some(serial) if self.revoked_serials.iter().any(|r| r == &serial) => {
+                    return Err(TlsError::General(
+                        "Client certificate has been revoked.".to_string(),
+                    ));
+                }
+                Some(_) => (),
+                None if !self.allow_unknown => {
+                    return Err(TlsError::General(
+                        "Unable to determine client certificate revocation status.".to_string(),
+                    ));
+                }
+                None => (),
+            }
+        }
+
+        Ok(verified)
+    }
+}
+
+/// Authenticated mTLS identities extracted during `verify_client_cert`,
+/// keyed by a fingerprint of the end-entity certificate. `verify_client_cert`
+/// has no way to hand its result back to the session beyond accept/reject,
+/// so the session layer looks the identity up here via
+/// [`take_client_cert_identity`] once it has the peer's certificate from the
+/// completed handshake, and stashes it on `Session::data.tls_client_subject`.
+static CLIENT_CERT_IDENTITIES: once_cell::sync::Lazy<std::sync::Mutex<std::collections::HashMap<Vec<u8>, String>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Looks up and removes the identity captured for `cert` by
+/// `IdentityCapturingVerifier` during the TLS handshake.
+pub fn take_client_cert_identity(cert: &Certificate) -> Option<String> {
+    CLIENT_CERT_IDENTITIES.lock().unwrap().remove(cert.0.as_slice())
+}
+
+/// Wraps a base `ClientCertVerifier` to additionally extract the verified
+/// peer certificate's subject/SAN and publish it via
+/// [`take_client_cert_identity`], so a successful mTLS handshake actually
+/// populates `Session::data.tls_client_subject` instead of the extraction
+/// logic in [`client_cert_identity`] going unused.
+struct IdentityCapturingVerifier {
+    inner: Arc<dyn ClientCertVerifier>,
+}
+
+impl ClientCertVerifier for IdentityCapturingVerifier {
+    fn offer_client_auth(&self) -> bool {
+        self.inner.offer_client_auth()
+    }
+
+    fn client_auth_mandatory(&self) -> Option<bool> {
+        self.inner.client_auth_mandatory()
+    }
+
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        self.inner.client_auth_root_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        now: SystemTime,
+    ) -> Result<ClientCertVerified, TlsError> {
+        let verified = self.inner.verify_client_cert(end_entity, intermediates, now)?;
+
+        if let Some(identity) = client_cert_identity(end_entity) {
+            CLIENT_CERT_IDENTITIES
+                .lock()
+                .unwrap()
+                .insert(end_entity.0.clone(), identity);
+        }
+
+        Ok(verified)
+    }
+}
+
+fn certificate_serial(cert: &Certificate) -> Option<Vec<u8>> {
+    x509_parser::parse_x509_certificate(cert.as_ref())
+        .ok()
+        .map(|(_, cert)| cert.raw_serial().to_vec())
+}
+
+/// Parses a PEM or DER-encoded CRL file and returns the serial numbers of
+/// every certificate it revokes.
+fn parse_crl(path: &str) -> Result<Vec<Vec<u8>>, String> {
+    let data = std::fs::read(path).map_err(|err| err.to_string())?;
+    let der = if data.starts_with(b"-----BEGIN") {
+        let (_, pem) = x509_parser::pem::parse_x509_pem(&data).map_err(|err| err.to_string())?;
+        pem.contents
+    } else {
+        data
+    };
+    let (_, crl) = x509_parser::revocation_list::CertificateRevocationList::from_der(&der)
+        .map_err(|err| err.to_string())?;
+    Ok(crl
+        .iter_revoked_certificates()
+        .map(|entry| entry.raw_serial().to_vec())
+        .collect())
+}
+
+/// Extracts a human-readable identity (subject CN, falling back to the
+/// first SAN) from an authenticated mTLS peer certificate, for stashing on
+/// the `Session` so `handle_rcpt_to` can key relay decisions on it.
+pub fn client_cert_identity(cert: &rustls::Certificate) -> Option<String> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref()).ok()?;
+    parsed
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string)
+        .or_else(|| {
+            parsed
+                .subject_alternative_name()
+                .ok()
+                .flatten()
+                .and_then(|san| san.value.general_names.first().map(|gn| gn.to_string()))
+        })
+}
+
 impl ParseValue for ServerProtocol {
     fn parse_value(key: impl AsKey, value: &str) -> super::Result<Self> {
         if value.eq_ignore_ascii_case("smtp") {
@@ -300,33 +791,15 @@ impl ParseValue for SocketAddr {
     }
 }
 
-impl ParseValue for SupportedCipherSuite {
-    fn parse_value(key: impl AsKey, value: &str) -> super::Result<Self> {
-        Ok(match value {
-            // TLS1.3 suites
-            "TLS13_AES_256_GCM_SHA384" => TLS13_AES_256_GCM_SHA384,
-            "TLS13_AES_128_GCM_SHA256" => TLS13_AES_128_GCM_SHA256,
-            "TLS13_CHACHA20_POLY1305_SHA256" => TLS13_CHACHA20_POLY1305_SHA256,
-            // TLS1.2 suites
-            "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384" => TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
-            "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256" => TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
-            "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256" => {
-                TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256
-            }
-            "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384" => TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
-            "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256" => TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
-            "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256" => {
-                TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
-            }
-            cipher => {
-                return Err(format!(
-                    "Unsupported TLS cipher suite {:?} found in key {:?}",
-                    cipher,
-                    key.as_key()
-                ))
-            }
-        })
-    }
+/// Resolves a cipher suite `name` against `ALL_CIPHER_SUITES`, the suites
+/// this build's rustls backend actually advertises, rather than a fixed
+/// match arm of compiled-in constants — a new suite only needs adding to
+/// `ALL_CIPHER_SUITES` for the parser to accept its name.
+fn find_cipher_suite(name: &str) -> Option<SupportedCipherSuite> {
+    ALL_CIPHER_SUITES
+        .iter()
+        .find(|suite| format!("{:?}", suite.suite()) == name)
+        .copied()
 }
 
 #[cfg(test)]
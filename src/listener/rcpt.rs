@@ -43,7 +43,7 @@ impl<T: AsyncWrite + AsyncRead + Unpin> Session<T> {
                             .write(b"451 4.4.3 Unable to verify address at this time.\r\n")
                             .await;
                     }
-                } else if !self.params.rcpt_relay {
+                } else if !self.params.rcpt_relay && self.data.tls_client_subject.is_none() {
                     return self.rcpt_error(b"550 5.1.2 Relay not allowed.\r\n").await;
                 }
             } else {
@@ -51,7 +51,7 @@ impl<T: AsyncWrite + AsyncRead + Unpin> Session<T> {
                     .write(b"451 4.4.3 Unable to verify address at this time.\r\n")
                     .await;
             }
-        } else if !self.params.rcpt_relay {
+        } else if !self.params.rcpt_relay && self.data.tls_client_subject.is_none() {
             return self.rcpt_error(b"550 5.1.2 Relay not allowed.\r\n").await;
         }
 
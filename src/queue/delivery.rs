@@ -1,15 +1,25 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::Arc,
-    time::{Duration, Instant},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
 
 use mail_send::SmtpClient;
+use once_cell::sync::Lazy;
 use rand::{seq::SliceRandom, Rng};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, Error as TlsError, ServerName,
+};
+use sha2::{Digest, Sha256, Sha512};
 use smtp_proto::{Severity, MAIL_REQUIRETLS};
 
-use crate::{config::RelayHost, core::Core};
+use crate::{
+    config::{RelayHost, ServerProtocol},
+    core::Core,
+};
 
 use super::{
     manager::Queue,
@@ -140,13 +150,84 @@ impl DeliveryAttempt {
                         }
                     };
 
+                // Enforce MTA-STS (RFC 8461), unless the route was pinned via next-hop.
+                let mta_sts_policy = if matches!(remote_hosts.first(), Some(RemoteHost::MX(_))) {
+                    core.fetch_mta_sts_policy(&domain.domain).await
+                } else {
+                    None
+                };
+                let mta_sts_enforce = matches!(
+                    &mta_sts_policy,
+                    Some(policy) if policy.mode == MtaStsMode::Enforce
+                );
+                let mut remote_hosts = if let Some(policy) = &mta_sts_policy {
+                    let filtered: Vec<_> = remote_hosts
+                        .iter()
+                        .filter(|host| policy.matches(host.hostname()))
+                        .cloned()
+                        .collect();
+                    match policy.mode {
+                        MtaStsMode::Enforce => {
+                            if filtered.is_empty() {
+                                domain.set_status(
+                                    Status::TemporaryFailure(Error::DNSError(format!(
+                                        "No MX host for {:?} matches the MTA-STS policy.",
+                                        domain.domain
+                                    ))),
+                                    queue_config.retry.eval(&envelope).await,
+                                );
+                                continue 'next_domain;
+                            }
+                            filtered
+                        }
+                        MtaStsMode::Testing => {
+                            // RFC 8461 section 11.2: testing mode never
+                            // restricts delivery, so the unfiltered host list
+                            // is always the one actually tried; a mismatch is
+                            // only logged here.
+                            if filtered.len() != remote_hosts.len() {
+                                tracing::info!(
+                                    parent: &span,
+                                    context = "mta-sts",
+                                    event = "policy-mismatch",
+                                    domain = %domain.domain,
+                                    "One or more MX hosts do not match the MTA-STS testing policy."
+                                );
+                            }
+                            remote_hosts
+                        }
+                        MtaStsMode::None => remote_hosts,
+                    }
+                } else {
+                    remote_hosts
+                };
+
                 // Try delivering message
                 let max_multihomed = *queue_config.max_multihomed.eval(&envelope).await;
                 let mut last_status = Status::Scheduled;
-                'next_host: for remote_host in &remote_hosts {
-                    // Obtain source and remote IPs
+                let mut fallback_tried = false;
+                let mut host_idx = 0;
+                'next_host: loop {
+                    if host_idx >= remote_hosts.len() {
+                        // All primary hosts failed without a permanent rejection:
+                        // give the configured smart-host relay one last try before
+                        // giving up on the domain for this attempt.
+                        if !fallback_tried && matches!(last_status, Status::TemporaryFailure(_)) {
+                            fallback_tried = true;
+                            if let Some(next_hop) = queue_config.fallback_relay.eval(&envelope).await
+                            {
+                                remote_hosts.push(RemoteHost::Relay(next_hop));
+                                continue 'next_host;
+                            }
+                        }
+                        break;
+                    }
+                    let remote_host = &remote_hosts[host_idx];
+                    host_idx += 1;
+
+                    // Obtain a family-interleaved, RFC 8305-ordered address list
                     envelope.mx = remote_host.hostname();
-                    let (source_ip, remote_ips) = match core
+                    let resolved_hosts = match core
                         .resolve_host(remote_host, &envelope, max_multihomed)
                         .await
                     {
@@ -157,55 +238,112 @@ impl DeliveryAttempt {
                         }
                     };
 
-                    // Try each IP address
-                    envelope.local_ip = source_ip.unwrap_or(no_ip);
-                    'next_ip: for remote_ip in remote_ips {
-                        // Throttle remote host
-                        let mut in_flight_host = Vec::new();
-                        envelope.remote_ip = remote_ip;
-                        for throttle in &queue_config.throttle.host {
-                            if let Err(err) = core
-                                .queue
-                                .is_allowed(throttle, &envelope, &mut in_flight_host, &span)
-                                .await
-                            {
-                                domain.set_throttle_error(err, &mut on_hold);
-                                continue 'next_domain;
-                            }
+                    // Throttle the remote host using the first candidate address;
+                    // a rejection here holds back the whole attempt, as before.
+                    let mut in_flight_host = Vec::new();
+                    envelope.remote_ip = resolved_hosts[0].ip;
+                    for throttle in &queue_config.throttle.host {
+                        if let Err(err) = core
+                            .queue
+                            .is_allowed(throttle, &envelope, &mut in_flight_host, &span)
+                            .await
+                        {
+                            domain.set_throttle_error(err, &mut on_hold);
+                            continue 'next_domain;
                         }
+                    }
 
-                        // Connect
-                        let mut smtp_client = match if let Some(ip_addr) = source_ip {
-                            SmtpClient::connect_using(
-                                ip_addr,
-                                SocketAddr::new(remote_ip, remote_host.port()),
-                                *queue_config.timeout_connect.eval(&envelope).await,
-                            )
-                            .await
-                        } else {
-                            SmtpClient::connect(
-                                SocketAddr::new(remote_ip, remote_host.port()),
-                                *queue_config.timeout_connect.eval(&envelope).await,
-                            )
-                            .await
-                        } {
-                            Ok(smtp_client) => smtp_client,
-                            Err(err) => {
+                    // Happy Eyeballs (RFC 8305): race a staggered connection attempt
+                    // per interleaved address, keep the first to succeed.
+                    let timeout_connect = *queue_config.timeout_connect.eval(&envelope).await;
+                    let mut attempts = tokio::task::JoinSet::new();
+                    for (pos, resolved) in resolved_hosts.iter().enumerate() {
+                        let stagger = Duration::from_millis(250) * pos as u32;
+                        let remote_ip = resolved.ip;
+                        let source_ip = resolved.source_ip;
+                        let port = remote_host.port();
+                        attempts.spawn(async move {
+                            if !stagger.is_zero() {
+                                tokio::time::sleep(stagger).await;
+                            }
+                            let result = if let Some(ip_addr) = source_ip {
+                                SmtpClient::connect_using(
+                                    ip_addr,
+                                    SocketAddr::new(remote_ip, port),
+                                    timeout_connect,
+                                )
+                                .await
+                            } else {
+                                SmtpClient::connect(SocketAddr::new(remote_ip, port), timeout_connect)
+                                    .await
+                            };
+                            (remote_ip, source_ip, result)
+                        });
+                    }
+
+                    let mut smtp_client = None;
+                    while let Some(result) = attempts.join_next().await {
+                        match result {
+                            Ok((remote_ip, source_ip, Ok(client))) => {
+                                envelope.remote_ip = remote_ip;
+                                envelope.local_ip = source_ip.unwrap_or(no_ip);
+                                smtp_client = Some(client);
+                                break;
+                            }
+                            Ok((_, _, Err(err))) => {
                                 last_status =
                                     Status::from(("Failed to connect to", envelope.mx, err));
-                                continue 'next_ip;
                             }
-                        };
+                            Err(_) => (),
+                        }
+                    }
+                    attempts.abort_all();
+
+                    let mut smtp_client = match smtp_client {
+                        Some(smtp_client) => smtp_client,
+                        None => continue 'next_host,
+                    };
 
+                    {
                         // Obtain TLS strategy
                         let tls_strategy = *queue_config.encryption.eval(&envelope).await;
+                        let dane_connector;
                         let tls_connector = if tls_strategy.is_dane() {
-                            todo!()
-                        } else if !remote_host.allow_invalid_certs() {
+                            match core.build_dane_connector(&envelope).await {
+                                Ok(Some(connector)) => {
+                                    dane_connector = connector;
+                                    &dane_connector
+                                }
+                                Ok(None) => {
+                                    // No usable TLSA records were found but the lookup
+                                    // was DNSSEC-authenticated: fall back to opportunistic TLS.
+                                    if !remote_host.allow_invalid_certs() {
+                                        &core.queue.connectors.pki_verify
+                                    } else {
+                                        &core.queue.connectors.dummy_verify
+                                    }
+                                }
+                                Err(status) => {
+                                    last_status = status;
+                                    continue 'next_host;
+                                }
+                            }
+                        } else if mta_sts_enforce || !remote_host.allow_invalid_certs() {
                             &core.queue.connectors.pki_verify
                         } else {
                             &core.queue.connectors.dummy_verify
                         };
+                        // LMTP relays (e.g. Dovecot/Cyrus LDAs) reply with one status
+                        // line per RCPT after DATA, so each recipient's outcome must
+                        // be recorded individually rather than collapsed to one status.
+                        let is_lmtp = remote_host.protocol() == ServerProtocol::Lmtp;
+                        let negotiated_policy = if tls_strategy.is_dane() {
+                            TlsRptPolicy::Dane
+                        } else if mta_sts_policy.is_some() {
+                            TlsRptPolicy::Sts
+                        } else {
+                            TlsRptPolicy::NoPolicy
+                        };
 
                         let delivery_result = if !remote_host.implicit_tls() {
                             // Read greeting
@@ -221,22 +359,47 @@ impl DeliveryAttempt {
                             smtp_client.timeout = *queue_config.timeout_tls.eval(&envelope).await;
                             match try_start_tls(smtp_client, tls_connector, envelope.mx).await {
                                 Ok(StartTlsResult::Success { smtp_client }) => {
+                                    core.record_tls_result(
+                                        &envelope,
+                                        TlsRptResultType::Successful,
+                                        negotiated_policy,
+                                    )
+                                    .await;
                                     // Deliver message over TLS
-                                    self.message
-                                        .deliver(
-                                            smtp_client,
-                                            recipients
-                                                .iter_mut()
-                                                .filter(|r| r.domain_idx == domain_idx),
-                                            &core.queue,
-                                        )
-                                        .await
+                                    if is_lmtp {
+                                        self.message
+                                            .deliver_lmtp(
+                                                smtp_client,
+                                                recipients
+                                                    .iter_mut()
+                                                    .filter(|r| r.domain_idx == domain_idx),
+                                                &core.queue,
+                                            )
+                                            .await
+                                    } else {
+                                        self.message
+                                            .deliver(
+                                                smtp_client,
+                                                recipients
+                                                    .iter_mut()
+                                                    .filter(|r| r.domain_idx == domain_idx),
+                                                &core.queue,
+                                            )
+                                            .await
+                                    }
                                 }
                                 Ok(StartTlsResult::Unavailable {
                                     response,
                                     smtp_client,
                                 }) => {
+                                    core.record_tls_result(
+                                        &envelope,
+                                        TlsRptResultType::StartTlsNotSupported,
+                                        negotiated_policy,
+                                    )
+                                    .await;
                                     if tls_strategy.is_tls_required()
+                                        || mta_sts_enforce
                                         || (self.message.flags & MAIL_REQUIRETLS) != 0
                                     {
                                         last_status = Status::from((
@@ -245,8 +408,18 @@ impl DeliveryAttempt {
                                             mail_send::Error::UnexpectedReply(response),
                                         ));
                                         continue 'next_host;
-                                    } else {
+                                    } else if is_lmtp {
                                         // TLS is not required, proceed in plain-text
+                                        self.message
+                                            .deliver_lmtp(
+                                                smtp_client,
+                                                recipients
+                                                    .iter_mut()
+                                                    .filter(|r| r.domain_idx == domain_idx),
+                                                &core.queue,
+                                            )
+                                            .await
+                                    } else {
                                         self.message
                                             .deliver(
                                                 smtp_client,
@@ -259,6 +432,12 @@ impl DeliveryAttempt {
                                     }
                                 }
                                 Err(status) => {
+                                    core.record_tls_result(
+                                        &envelope,
+                                        tls_rpt_result_type(&status, negotiated_policy),
+                                        negotiated_policy,
+                                    )
+                                    .await;
                                     last_status = status;
                                     continue 'next_host;
                                 }
@@ -270,6 +449,12 @@ impl DeliveryAttempt {
                                 match into_tls(smtp_client, tls_connector, envelope.mx).await {
                                     Ok(smtp_client) => smtp_client,
                                     Err(status) => {
+                                        core.record_tls_result(
+                                            &envelope,
+                                            tls_rpt_result_type(&status, negotiated_policy),
+                                            negotiated_policy,
+                                        )
+                                        .await;
                                         last_status = status;
                                         continue 'next_host;
                                     }
@@ -285,13 +470,29 @@ impl DeliveryAttempt {
                             }
 
                             // Deliver message
-                            self.message
-                                .deliver(
-                                    smtp_client,
-                                    recipients.iter_mut().filter(|r| r.domain_idx == domain_idx),
-                                    &core.queue,
-                                )
-                                .await
+                            core.record_tls_result(
+                                &envelope,
+                                TlsRptResultType::Successful,
+                                negotiated_policy,
+                            )
+                            .await;
+                            if is_lmtp {
+                                self.message
+                                    .deliver_lmtp(
+                                        smtp_client,
+                                        recipients.iter_mut().filter(|r| r.domain_idx == domain_idx),
+                                        &core.queue,
+                                    )
+                                    .await
+                            } else {
+                                self.message
+                                    .deliver(
+                                        smtp_client,
+                                        recipients.iter_mut().filter(|r| r.domain_idx == domain_idx),
+                                        &core.queue,
+                                    )
+                                    .await
+                            }
                         };
 
                         // Update status for domain and continue with next domain
@@ -333,6 +534,7 @@ impl DeliveryAttempt {
     }
 }
 
+#[derive(Clone, Copy)]
 enum RemoteHost<'x> {
     Relay(&'x RelayHost),
     MX(&'x str),
@@ -379,69 +581,748 @@ impl<'x> RemoteHost<'x> {
             RemoteHost::Relay(host) => host.tls_implicit,
         }
     }
+
+    fn protocol(&self) -> ServerProtocol {
+        match self {
+            RemoteHost::MX(_) => ServerProtocol::Smtp,
+            RemoteHost::Relay(host) => host.protocol,
+        }
+    }
+}
+
+/// A `rustls` certificate verifier that authenticates the server against a
+/// DNSSEC-validated TLSA RRset, per RFC 7672.
+struct DaneVerifier {
+    entries: Vec<TlsaEntry>,
+    has_end_entities: bool,
+    has_intermediates: bool,
+}
+
+struct TlsaEntry {
+    is_end_entity: bool,
+    is_spki: bool,
+    matching_type: TlsaMatchingType,
+    data: Vec<u8>,
+}
+
+#[derive(Clone, Copy)]
+enum TlsaMatchingType {
+    Full,
+    Sha256,
+    Sha512,
+}
+
+impl TlsaEntry {
+    fn matches(&self, cert: &Certificate, spki: Option<&[u8]>) -> bool {
+        let selected = if self.is_spki {
+            match spki {
+                Some(spki) => spki,
+                None => return false,
+            }
+        } else {
+            cert.as_ref()
+        };
+
+        match self.matching_type {
+            TlsaMatchingType::Full => self.data == selected,
+            TlsaMatchingType::Sha256 => self.data[..] == Sha256::digest(selected)[..],
+            TlsaMatchingType::Sha512 => self.data[..] == Sha512::digest(selected)[..],
+        }
+    }
+}
+
+impl ServerCertVerifier for DaneVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        // DANE-EE (usage 3): match the leaf certificate regardless of
+        // whether a PKIX chain to a trust anchor can be built.
+        if self.has_end_entities {
+            for entry in self.entries.iter().filter(|e| e.is_end_entity) {
+                if entry.matches(end_entity, spki_of(end_entity).as_deref()) {
+                    return Ok(ServerCertVerified::assertion());
+                }
+            }
+        }
+
+        // DANE-TA (usage 2): a certificate in the presented chain must match
+        // the trust anchor record, *and* the end-entity certificate must
+        // cryptographically chain up to it (a matching hash alone isn't
+        // enough, since CA certificates are public and an attacker could
+        // pair the real CA cert with a self-issued leaf it never signed).
+        if self.has_intermediates {
+            for (pos, cert) in std::iter::once(end_entity).chain(intermediates.iter()).enumerate()
+            {
+                for entry in self.entries.iter().filter(|e| !e.is_end_entity) {
+                    if entry.matches(cert, spki_of(cert).as_deref()) {
+                        // The anchor may be the leaf itself (self-issued,
+                        // pos == 0) or any certificate in `intermediates`;
+                        // path-build only through the certificates below it.
+                        return verify_chain_to_dane_anchor(
+                            end_entity,
+                            if pos == 0 { &[] } else { &intermediates[..pos - 1] },
+                            cert,
+                            server_name,
+                            now,
+                        );
+                    }
+                }
+            }
+        }
+
+        Err(TlsError::General(
+            "No matching TLSA record found for the presented certificate chain.".to_string(),
+        ))
+    }
+}
+
+/// Validates that `end_entity` cryptographically chains up to `anchor`
+/// (which matched a DANE-TA TLSA record), via the intermediates presented
+/// below it, and that it's valid for `server_name` — real PKIX path
+/// validation, not just a hash match against some certificate in the chain.
+fn verify_chain_to_dane_anchor(
+    end_entity: &Certificate,
+    intermediates: &[Certificate],
+    anchor: &Certificate,
+    server_name: &ServerName,
+    now: SystemTime,
+) -> Result<ServerCertVerified, TlsError> {
+    let trust_anchor = webpki::TrustAnchor::try_from_cert_der(anchor.as_ref())
+        .map_err(|err| TlsError::General(format!("Invalid DANE-TA anchor certificate: {}", err)))?;
+    let trust_anchors = webpki::TlsServerTrustAnchors(std::slice::from_ref(&trust_anchor));
+
+    let cert = webpki::EndEntityCert::try_from(end_entity.as_ref())
+        .map_err(|err| TlsError::General(format!("Invalid end-entity certificate: {}", err)))?;
+    let intermediates: Vec<&[u8]> = intermediates.iter().map(|cert| cert.as_ref()).collect();
+    let webpki_now = webpki::Time::try_from(now)
+        .map_err(|_| TlsError::General("Invalid current time".to_string()))?;
+
+    cert.verify_is_valid_tls_server_cert(
+        webpki::ALL_SIGALGS,
+        &trust_anchors,
+        &intermediates,
+        webpki_now,
+    )
+    .map_err(|err| {
+        TlsError::General(format!(
+            "Certificate chain does not validate up to the DANE-TA anchor: {}",
+            err
+        ))
+    })?;
+
+    if let ServerName::DnsName(name) = server_name {
+        let dns_name = webpki::DnsNameRef::try_from_ascii_str(name.as_ref())
+            .map_err(|_| TlsError::General("Invalid DNS name".to_string()))?;
+        cert.verify_is_valid_for_dns_name(dns_name).map_err(|err| {
+            TlsError::General(format!("Certificate not valid for {:?}: {}", name, err))
+        })?;
+    }
+
+    Ok(ServerCertVerified::assertion())
+}
+
+/// Extracts the DER-encoded SubjectPublicKeyInfo from a certificate, for
+/// TLSA selector 1, by parsing the certificate with `x509_parser`. Returns
+/// `None` when the certificate cannot be parsed.
+fn spki_of(cert: &Certificate) -> Option<Vec<u8>> {
+    x509_parser::parse_x509_certificate(cert.as_ref())
+        .ok()
+        .map(|(_, cert)| cert.public_key().raw.to_vec())
+}
+
+static MTA_STS_CACHE: Lazy<Mutex<HashMap<String, CachedMtaSts>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Shared client for MTA-STS policy fetches, bounded by an explicit timeout
+/// so a slow or unresponsive `mta-sts.<domain>` host cannot stall a delivery
+/// attempt ahead of the usual `timeout_connect`/`timeout_tls` machinery.
+static MTA_STS_HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+struct CachedMtaSts {
+    id: String,
+    expires: Instant,
+    policy: Option<MtaStsPolicy>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum MtaStsMode {
+    Enforce,
+    Testing,
+    None,
+}
+
+#[derive(Clone)]
+struct MtaStsPolicy {
+    mode: MtaStsMode,
+    mx_patterns: Vec<String>,
+    max_age: Duration,
+}
+
+impl MtaStsPolicy {
+    /// Parses the `https://mta-sts.<domain>/.well-known/mta-sts.txt` policy
+    /// body, a sequence of `key: value` lines.
+    fn parse(text: &str) -> Option<Self> {
+        let mut mode = None;
+        let mut mx_patterns = Vec::new();
+        let mut max_age = Duration::from_secs(86400);
+
+        for line in text.lines() {
+            let (key, value) = line.split_once(':')?;
+            match key.trim() {
+                "mode" => {
+                    mode = Some(match value.trim() {
+                        "enforce" => MtaStsMode::Enforce,
+                        "testing" => MtaStsMode::Testing,
+                        _ => MtaStsMode::None,
+                    });
+                }
+                "mx" => mx_patterns.push(value.trim().to_lowercase()),
+                "max_age" => {
+                    if let Ok(secs) = value.trim().parse() {
+                        max_age = Duration::from_secs(secs);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Some(MtaStsPolicy {
+            mode: mode?,
+            mx_patterns,
+            max_age,
+        })
+    }
+
+    /// Matches an MX hostname against the policy's `mx` patterns, which may
+    /// carry a single leading `*.` wildcard label. Per RFC 8461 §4.1 the
+    /// wildcard matches exactly one additional label, so `*.example.com`
+    /// matches `a.example.com` but not `a.b.example.com`.
+    fn matches(&self, hostname: &str) -> bool {
+        let hostname = hostname.to_lowercase();
+        self.mx_patterns.iter().any(|pattern| {
+            if let Some(suffix) = pattern.strip_prefix("*.") {
+                hostname.strip_suffix(suffix).is_some_and(|prefix| {
+                    prefix.ends_with('.') && prefix[..prefix.len() - 1].find('.').is_none() && prefix.len() > 1
+                })
+            } else {
+                hostname == *pattern
+            }
+        })
+    }
+}
+
+/// Extracts the `id` field from a `v=STSv1; id=...` TXT record.
+fn mta_sts_txt_id(txt: &str) -> Option<&str> {
+    if !txt.trim_start().starts_with("v=STSv1") {
+        return None;
+    }
+    txt.split(';')
+        .find_map(|part| part.trim().strip_prefix("id="))
+}
+
+/// Extracts the `rua=` report endpoints from a `v=TLSRPTv1; rua=...`
+/// `_smtp._tls.<domain>` TXT record (RFC 8460 section 3), a comma-separated
+/// list of `mailto:` and/or `https:` URIs.
+fn tls_rpt_rua_uris(txt: &str) -> Vec<String> {
+    if !txt.trim_start().starts_with("v=TLSRPTv1") {
+        return Vec::new();
+    }
+    txt.split(';')
+        .find_map(|part| part.trim().strip_prefix("rua="))
+        .map(|rua| rua.split(',').map(|uri| uri.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// A TLS negotiation outcome, as defined by the `result-type` enumeration
+/// in RFC 8460.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TlsRptResultType {
+    Successful,
+    StartTlsNotSupported,
+    CertificateExpired,
+    ValidationFailure,
+    DaneRequired,
+}
+
+impl TlsRptResultType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TlsRptResultType::Successful => "successful",
+            TlsRptResultType::StartTlsNotSupported => "starttls-not-supported",
+            TlsRptResultType::CertificateExpired => "certificate-expired",
+            TlsRptResultType::ValidationFailure => "validation-failure",
+            TlsRptResultType::DaneRequired => "dane-required",
+        }
+    }
+}
+
+/// The policy type that was enforced on a given connection, for inclusion
+/// in the `policies` section of an RFC 8460 aggregate report.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TlsRptPolicy {
+    Sts,
+    Dane,
+    NoPolicy,
 }
 
+impl TlsRptPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TlsRptPolicy::Sts => "sts",
+            TlsRptPolicy::Dane => "dane",
+            TlsRptPolicy::NoPolicy => "no-policy-found",
+        }
+    }
+}
+
+/// Classifies a delivery `Status` into the closest RFC 8460 result-type.
+/// The session layer surfaces TLS failures as `Error::ConnectionError`
+/// strings rather than a dedicated variant, so certificate expiry is
+/// detected from the message text; anything else falls back to DANE's
+/// mandatory-failure type or plain validation failure, per policy.
+fn tls_rpt_result_type(status: &Status, policy: TlsRptPolicy) -> TlsRptResultType {
+    let message = if let Status::TemporaryFailure(err) | Status::PermanentFailure(err) = status {
+        err.to_string()
+    } else {
+        String::new()
+    };
+    if message.to_lowercase().contains("expired") {
+        TlsRptResultType::CertificateExpired
+    } else if policy == TlsRptPolicy::Dane {
+        TlsRptResultType::DaneRequired
+    } else {
+        TlsRptResultType::ValidationFailure
+    }
+}
+
+#[derive(Default)]
+struct TlsRptCounters {
+    success: u64,
+    failure: u64,
+    failure_details: HashMap<TlsRptResultType, u64>,
+}
+
+#[derive(Eq, PartialEq, Hash)]
+struct TlsRptKey {
+    policy_domain: String,
+    mx_host: String,
+    remote_ip: IpAddr,
+    policy: TlsRptPolicy,
+}
+
+static TLS_RPT_AGGREGATE: Lazy<Mutex<HashMap<TlsRptKey, TlsRptCounters>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The `rua` report endpoint(s) `_smtp._tls.<domain>` publishes, if any,
+/// cached so every delivery attempt doesn't repeat the TXT lookup. An empty
+/// `Vec` means the domain has no TLS-RPT record.
+static TLS_RPT_RUA: Lazy<Mutex<HashMap<String, (Vec<String>, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Shared client for delivering TLS-RPT reports to `https:` rua endpoints.
+static TLS_RPT_HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
 impl Core {
-    async fn resolve_host(
+    /// Accumulates a single connection's TLS outcome into the daily
+    /// aggregate for `envelope.domain`, if that domain publishes a TLS-RPT
+    /// `rua` endpoint under `_smtp._tls.<domain>`.
+    async fn record_tls_result(
         &self,
-        remote_host: &RemoteHost<'_>,
         envelope: &QueueEnvelope<'_>,
-        max_multihomed: usize,
-    ) -> Result<(Option<IpAddr>, Vec<IpAddr>), Status> {
-        let mut remote_ips = Vec::new();
-        let mut source_ip = None;
+        result: TlsRptResultType,
+        policy: TlsRptPolicy,
+    ) {
+        if self.tls_rpt_rua(envelope.domain).await.is_empty() {
+            return;
+        }
 
-        for (pos, remote_ip) in self
+        let key = TlsRptKey {
+            policy_domain: envelope.domain.to_string(),
+            mx_host: envelope.mx.to_string(),
+            remote_ip: envelope.remote_ip,
+            policy,
+        };
+        let mut aggregate = TLS_RPT_AGGREGATE.lock().unwrap();
+        let counters = aggregate.entry(key).or_default();
+        if result == TlsRptResultType::Successful {
+            counters.success += 1;
+        } else {
+            counters.failure += 1;
+            *counters.failure_details.entry(result).or_insert(0) += 1;
+        }
+    }
+
+    async fn tls_rpt_rua(&self, domain: &str) -> Vec<String> {
+        if let Some((rua, expires)) = TLS_RPT_RUA.lock().unwrap().get(domain) {
+            if *expires > Instant::now() {
+                return rua.clone();
+            }
+        }
+
+        let rua = self
             .resolver
-            .ip_lookup(remote_host.fqdn_hostname().as_ref())
-            .await?
-            .take(max_multihomed)
-            .enumerate()
-        {
-            if pos == 0 {
-                if remote_ip.is_ipv4() {
-                    let source_ips = self.queue.config.source_ipv4.eval(envelope).await;
-                    match source_ips.len().cmp(&1) {
-                        std::cmp::Ordering::Equal => {
-                            source_ip = IpAddr::from(*source_ips.first().unwrap()).into();
-                        }
-                        std::cmp::Ordering::Greater => {
-                            source_ip = IpAddr::from(
-                                source_ips[rand::thread_rng().gen_range(0..source_ips.len())],
-                            )
-                            .into();
-                        }
-                        std::cmp::Ordering::Less => (),
-                    }
+            .txt_lookup(format!("_smtp._tls.{}", domain))
+            .await
+            .map(|records| {
+                records
+                    .iter()
+                    .find_map(|txt| {
+                        let uris = tls_rpt_rua_uris(txt);
+                        (!uris.is_empty()).then_some(uris)
+                    })
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        TLS_RPT_RUA.lock().unwrap().insert(
+            domain.to_string(),
+            (rua.clone(), Instant::now() + Duration::from_secs(3600)),
+        );
+
+        rua
+    }
+
+    /// Serializes the accumulated per-domain counters into the JSON report
+    /// body defined by RFC 8460 and delivers each to its domain's `rua`
+    /// endpoint(s). Intended to be called once per reporting interval
+    /// (daily).
+    pub async fn flush_tls_rpt_reports(&self) {
+        let aggregate = std::mem::take(&mut *TLS_RPT_AGGREGATE.lock().unwrap());
+        for (key, counters) in aggregate {
+            let report = serde_json::json!({
+                "policies": [{
+                    "policy": {
+                        "policy-type": key.policy.as_str(),
+                        "policy-domain": key.policy_domain,
+                    },
+                    "summary": {
+                        "total-successful-session-count": counters.success,
+                        "total-failure-session-count": counters.failure,
+                    },
+                    "failure-details": counters.failure_details.iter().map(|(result_type, count)| {
+                        serde_json::json!({
+                            "result-type": result_type.as_str(),
+                            "receiving-ip": key.remote_ip.to_string(),
+                            "receiving-mx-hostname": key.mx_host,
+                            "failed-session-count": count,
+                        })
+                    }).collect::<Vec<_>>(),
+                }],
+            });
+
+            let rua_uris = self.tls_rpt_rua(&key.policy_domain).await;
+            if rua_uris.is_empty() {
+                tracing::debug!(
+                    domain = key.policy_domain,
+                    "No TLS-RPT rua endpoint found for domain, dropping aggregate report."
+                );
+                continue;
+            }
+
+            for rua in &rua_uris {
+                let result = if let Some(url) = rua.strip_prefix("https:") {
+                    self.send_tls_rpt_report_https(&format!("https:{}", url), &report)
+                        .await
+                } else if let Some(address) = rua.strip_prefix("mailto:") {
+                    self.send_tls_rpt_report_mail(address, &key.policy_domain, &report)
+                        .await
                 } else {
-                    let source_ips = self.queue.config.source_ipv6.eval(envelope).await;
-                    match source_ips.len().cmp(&1) {
-                        std::cmp::Ordering::Equal => {
-                            source_ip = IpAddr::from(*source_ips.first().unwrap()).into();
-                        }
-                        std::cmp::Ordering::Greater => {
-                            source_ip = IpAddr::from(
-                                source_ips[rand::thread_rng().gen_range(0..source_ips.len())],
-                            )
-                            .into();
-                        }
-                        std::cmp::Ordering::Less => (),
-                    }
+                    tracing::debug!(
+                        domain = key.policy_domain,
+                        rua,
+                        "Unsupported TLS-RPT rua scheme, skipping."
+                    );
+                    continue;
+                };
+
+                if let Err(err) = result {
+                    tracing::warn!(
+                        domain = key.policy_domain,
+                        rua,
+                        "Failed to deliver TLS-RPT aggregate report: {}",
+                        err
+                    );
                 }
             }
-            remote_ips.push(remote_ip);
         }
+    }
 
-        // Make sure there is at least one IP address
-        if !remote_ips.is_empty() {
-            Ok((source_ip, remote_ips))
+    /// POSTs `report` to an `https:` rua endpoint, per RFC 8460 section 4.
+    async fn send_tls_rpt_report_https(
+        &self,
+        url: &str,
+        report: &serde_json::Value,
+    ) -> Result<(), String> {
+        let response = TLS_RPT_HTTP_CLIENT
+            .post(url)
+            .header("Content-Type", "application/tlsrpt+json")
+            .json(report)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        if response.status().is_success() {
+            Ok(())
         } else {
-            Err(Status::TemporaryFailure(Error::DNSError(format!(
+            Err(format!("HTTP status {}", response.status()))
+        }
+    }
+
+    /// Delivers `report` as a `mailto:` rua by connecting directly to the
+    /// recipient domain's MX, per RFC 8460 section 4.
+    async fn send_tls_rpt_report_mail(
+        &self,
+        rcpt: &str,
+        policy_domain: &str,
+        report: &serde_json::Value,
+    ) -> Result<(), String> {
+        let rcpt_domain = rcpt
+            .rsplit_once('@')
+            .map(|(_, d)| d)
+            .ok_or_else(|| format!("Invalid TLS-RPT rua address {:?}", rcpt))?;
+        let mx_list = self
+            .resolver
+            .mx_lookup(rcpt_domain)
+            .await
+            .map_err(|err| format!("MX lookup for {:?} failed: {}", rcpt_domain, err))?;
+        let mx_host = mx_list
+            .iter()
+            .flat_map(|mx| mx.exchanges.iter())
+            .next()
+            .ok_or_else(|| format!("No MX records found for {:?}", rcpt_domain))?;
+
+        let message = mail_builder::MessageBuilder::new()
+            .from(("TLS Reporting", format!("postmaster@{}", policy_domain)))
+            .to(rcpt)
+            .subject(format!("TLS Report for {}", policy_domain))
+            .text_body("This is an automated TLS-RPT aggregate report.")
+            .attachment(
+                "application/tlsrpt+json",
+                format!("{}-tlsrpt.json", policy_domain),
+                serde_json::to_vec(report).map_err(|err| err.to_string())?,
+            );
+
+        mail_send::SmtpClientBuilder::new(mx_host.as_str(), 25)
+            .timeout(Duration::from_secs(30))
+            .connect()
+            .await
+            .map_err(|err| err.to_string())?
+            .send(message)
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    /// Builds a one-off TLS connector for DANE (RFC 7672), keyed off the
+    /// TLSA RRset published under `_25._tcp.<mx>` (port 25 is used for every
+    /// host, relays included). Returns `Ok(None)`
+    /// when the lookup was DNSSEC-authenticated but no usable records were
+    /// published, so the caller can fall back per policy.
+    async fn build_dane_connector(
+        &self,
+        envelope: &QueueEnvelope<'_>,
+    ) -> Result<Option<Arc<rustls::ClientConfig>>, Status> {
+        let tlsa_domain = format!("_25._tcp.{}", envelope.mx);
+        let tlsa = match self.resolver.tlsa_lookup(&tlsa_domain).await {
+            Ok(tlsa) => tlsa,
+            Err(err) => return Err(Status::from(err)),
+        };
+
+        if !tlsa.dnssec_authenticated {
+            return Err(Status::TemporaryFailure(Error::DNSError(format!(
+                "TLSA lookup for {:?} is not DNSSEC-authenticated.",
+                envelope.mx
+            ))));
+        }
+
+        let mut entries = Vec::new();
+        for record in &tlsa.entries {
+            let matching_type = match record.matching_type {
+                0 => TlsaMatchingType::Full,
+                1 => TlsaMatchingType::Sha256,
+                2 => TlsaMatchingType::Sha512,
+                _ => continue,
+            };
+            match record.usage {
+                // DANE-EE and DANE-TA are the only usages meaningful for SMTP.
+                3 | 2 => entries.push(TlsaEntry {
+                    is_end_entity: record.usage == 3,
+                    is_spki: record.selector == 1,
+                    matching_type,
+                    data: record.data.clone(),
+                }),
+                _ => (),
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        let has_end_entities = entries.iter().any(|e| e.is_end_entity);
+        let has_intermediates = entries.iter().any(|e| !e.is_end_entity);
+        let verifier = DaneVerifier {
+            entries,
+            has_end_entities,
+            has_intermediates,
+        };
+
+        Ok(Some(Arc::new(
+            rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_custom_certificate_verifier(Arc::new(verifier))
+                .with_no_client_auth(),
+        )))
+    }
+
+    /// Looks up and, if necessary, refreshes the MTA-STS (RFC 8461) policy
+    /// for `domain`. Returns `None` when the domain has no policy, or when
+    /// it could not be fetched and no earlier policy for the domain is cached
+    /// (callers must treat that as "no policy"); a failed refetch returns the
+    /// previously cached policy.
+    async fn fetch_mta_sts_policy(&self, domain: &str) -> Option<MtaStsPolicy> {
+        let record = self
+            .resolver
+            .txt_lookup(format!("_mta-sts.{}", domain))
+            .await
+            .ok()?;
+        let policy_id = record
+            .iter()
+            .find_map(|txt| mta_sts_txt_id(txt))
+            .map(str::to_string)?;
+
+        let mut stale_policy = None;
+        if let Some(cached) = MTA_STS_CACHE.lock().unwrap().get(domain) {
+            if cached.id == policy_id && cached.expires > Instant::now() {
+                return cached.policy.clone();
+            }
+            // The `id` changed (or the cached entry aged out): keep the
+            // still-parsed policy around so a failed refetch doesn't
+            // silently downgrade this delivery to no-MTA-STS-enforcement
+            // (RFC 8461 §5.1 requires continuing to honor the last known
+            // policy when a refetch fails).
+            stale_policy = cached.policy.clone();
+        }
+
+        let fetched = async {
+            let policy_text = MTA_STS_HTTP_CLIENT
+                .get(format!("https://mta-sts.{}/.well-known/mta-sts.txt", domain))
+                .send()
+                .await
+                .ok()?
+                .text()
+                .await
+                .ok()?;
+            Some(MtaStsPolicy::parse(&policy_text))
+        }
+        .await;
+
+        let Some(policy) = fetched else {
+            return stale_policy;
+        };
+
+        MTA_STS_CACHE.lock().unwrap().insert(
+            domain.to_string(),
+            CachedMtaSts {
+                id: policy_id,
+                expires: Instant::now()
+                    + policy
+                        .as_ref()
+                        .map_or(Duration::from_secs(86400), |p| p.max_age),
+                policy: policy.clone(),
+            },
+        );
+
+        policy
+    }
+
+    /// Resolves `remote_host` to a family-interleaved, RFC 8305-ordered list
+    /// of candidate addresses, each paired with the source IP that should be
+    /// used to connect to it (selected per-family from `source_ipv4`/
+    /// `source_ipv6`, as before).
+    async fn resolve_host(
+        &self,
+        remote_host: &RemoteHost<'_>,
+        envelope: &QueueEnvelope<'_>,
+        max_multihomed: usize,
+    ) -> Result<Vec<ResolvedAddr>, Status> {
+        let fqdn = remote_host.fqdn_hostname();
+        let (ipv6_result, ipv4_result) =
+            tokio::join!(self.resolver.ipv6_lookup(fqdn.as_ref()), self.resolver.ipv4_lookup(fqdn.as_ref()));
+
+        let mut ipv6 = ipv6_result
+            .map(|addrs| addrs.iter().map(|ip| IpAddr::V6(*ip)).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter();
+        let mut ipv4 = ipv4_result
+            .map(|addrs| addrs.iter().map(|ip| IpAddr::V4(*ip)).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter();
+
+        // Interleave the two families (RFC 8305 favors IPv6-first) so a
+        // stalled address of one family doesn't delay trying the other.
+        let mut interleaved = Vec::new();
+        loop {
+            match (ipv6.next(), ipv4.next()) {
+                (Some(a), Some(b)) => {
+                    interleaved.push(a);
+                    interleaved.push(b);
+                }
+                (Some(a), None) => interleaved.push(a),
+                (None, Some(b)) => interleaved.push(b),
+                (None, None) => break,
+            }
+        }
+        interleaved.truncate(max_multihomed);
+
+        if interleaved.is_empty() {
+            return Err(Status::TemporaryFailure(Error::DNSError(format!(
                 "No IP addresses found for {:?}.",
                 envelope.mx
-            ))))
+            ))));
         }
+
+        let source_ipv4 = self.pick_source_ip(&self.queue.config.source_ipv4.eval(envelope).await);
+        let source_ipv6 = self.pick_source_ip(&self.queue.config.source_ipv6.eval(envelope).await);
+
+        Ok(interleaved
+            .into_iter()
+            .map(|ip| ResolvedAddr {
+                ip,
+                source_ip: if ip.is_ipv4() { source_ipv4 } else { source_ipv6 },
+            })
+            .collect())
     }
+
+    fn pick_source_ip<T: Into<IpAddr> + Copy>(&self, source_ips: &[T]) -> Option<IpAddr> {
+        match source_ips.len().cmp(&1) {
+            std::cmp::Ordering::Equal => Some(source_ips[0].into()),
+            std::cmp::Ordering::Greater => {
+                Some(source_ips[rand::thread_rng().gen_range(0..source_ips.len())].into())
+            }
+            std::cmp::Ordering::Less => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ResolvedAddr {
+    ip: IpAddr,
+    source_ip: Option<IpAddr>,
 }
 
 impl Domain {
@@ -523,4 +1404,23 @@ impl From<Box<Message>> for DeliveryAttempt {
             message,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MtaStsPolicy;
+
+    #[test]
+    fn mta_sts_wildcard_matches_single_label_only() {
+        let policy = MtaStsPolicy {
+            mode: super::MtaStsMode::Enforce,
+            mx_patterns: vec!["*.example.com".to_string()],
+            max_age: std::time::Duration::from_secs(86400),
+        };
+
+        assert!(policy.matches("mail.example.com"));
+        assert!(!policy.matches("example.com"));
+        assert!(!policy.matches("a.b.example.com"));
+        assert!(!policy.matches("evilexample.com"));
+    }
 }
\ No newline at end of file